@@ -1,15 +1,37 @@
 use rand::{thread_rng, Rng};
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::hash::Hash;
 use std::fmt::Debug;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Instant;
 
 const N_TRAINING_SAMPLES: usize = 100_000;
 
 fn main() {
     let mut searcher = CastleSearcher::new();
-    println!("{:?}", searcher.pathfind());
+
+    // pathfind only returns once is_goal fires, which this domain never does
+    // (see chunk0-1) -- run it on the side so it can't block the bounded
+    // searches below from reporting results.
+    let mut pathfinder = searcher.clone();
+    thread::spawn(move || {
+        pathfinder.pathfind();
+    });
+
+    println!("beam_search: {:?}", searcher.beam_search(10, 5).0.troops());
+
+    let equilibrium_response = searcher.fictitious_play(20, 2.0);
+    println!("fictitious_play: {:?}", equilibrium_response.troops());
+
+    let best = searcher.multi_start(4, 5.0);
+    println!("multi_start: {:?}", best.troops());
 }
 
 
@@ -27,6 +49,14 @@ impl Searchable for CastleSearcher {
         solution.neighbors()
     }
 
+    fn heuristic(&self, solution: &Self::Solution) -> f64 {
+        self.test_on_data(solution) as f64
+    }
+
+    fn fitness_bucket_count(&self) -> Option<usize> {
+        Some(N_TRAINING_SAMPLES)
+    }
+
     fn fitness_estimate(&mut self, solution: &Self::Solution) -> f64 {
         let fitness = self.test_on_data(solution) as f64;
 
@@ -57,7 +87,7 @@ impl Searchable for CastleSearcher {
 #[derive(Clone)]
 struct CastleSearcher {
     best_of_all_time: BinaryHeap<HeapEntry<Castle>>,
-    training_data: Vec<Castle>,
+    training_data: Arc<Vec<Castle>>,
 }
 
 impl CastleSearcher {
@@ -70,18 +100,97 @@ impl CastleSearcher {
 
         CastleSearcher {
             best_of_all_time: BinaryHeap::new(),
-            training_data
+            training_data: Arc::new(training_data),
         }
     }
 
     fn test_on_data(&self, solution: &Castle) -> usize {
-        let mut wins = 0;
+        let pool = eval_pool();
+        let chunk_size = self.training_data.len().div_ceil(pool.size);
+
+        let (sender, receiver) = mpsc::channel();
+        let mut n_jobs = 0;
 
-        for other in self.training_data.iter() {
-            if solution.does_win(other) { wins += 1 }
+        for chunk_start in (0..self.training_data.len()).step_by(chunk_size.max(1)) {
+            let chunk_end = (chunk_start + chunk_size).min(self.training_data.len());
+            let training_data = Arc::clone(&self.training_data);
+            let solution = solution.clone();
+            let sender = sender.clone();
+
+            pool.execute(move || {
+                let wins = training_data[chunk_start..chunk_end].iter().filter(|other| solution.does_win(other)).count();
+                sender.send(wins).unwrap();
+            });
+
+            n_jobs += 1;
         }
 
-        wins
+        drop(sender);
+        receiver.iter().take(n_jobs).sum()
+    }
+
+    fn multi_start(&mut self, n_workers: usize, time_limit: f64) -> Castle {
+        let (sender, receiver) = mpsc::channel();
+
+        let handles: Vec<_> = (0..n_workers).map(|_| {
+            let training_data = Arc::clone(&self.training_data);
+            let sender = sender.clone();
+
+            thread::spawn(move || {
+                let mut worker = CastleSearcher {
+                    best_of_all_time: BinaryHeap::new(),
+                    training_data,
+                };
+
+                let (best, fitness) = worker.anneal(time_limit);
+                sender.send(HeapEntry { value: fitness, item: best }).unwrap();
+            })
+        }).collect();
+
+        drop(sender);
+
+        for entry in receiver {
+            self.best_of_all_time.push(entry);
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        self.best_of_all_time.peek().unwrap().item.clone()
+    }
+
+    fn fictitious_play(&mut self, rounds: usize, time_limit_per_round: f64) -> Castle {
+        let mut best_response = self.start();
+        let mut best_exploitability = None;
+
+        for round in 0..rounds {
+            let (response, fitness) = self.anneal(time_limit_per_round);
+
+            let wins = fitness as usize;
+            let exploitability = wins as f64 / self.training_data.len() as f64;
+
+            println!("round {}: exploitability {:.4}", round, exploitability);
+
+            if best_exploitability.is_some_and(|best| exploitability <= best) {
+                break;
+            }
+            best_exploitability = Some(exploitability);
+            best_response = response.clone();
+
+            let response_and_neighbors: Vec<Castle> = std::iter::once(response.clone())
+                .chain(response.neighbors())
+                .collect();
+
+            let mut training_data = (*self.training_data).clone();
+            let n_replaced = response_and_neighbors.len().min(training_data.len());
+            training_data.drain(0..n_replaced);
+            training_data.extend(response_and_neighbors);
+
+            self.training_data = Arc::new(training_data);
+        }
+
+        best_response
     }
 }
 
@@ -170,6 +279,22 @@ trait Searchable: Clone {
 
     fn neighbors(&self, solution: &Self::Solution) -> Vec<Self::Solution>;
 
+    // Edge cost from `from` to `to`, added to `g`. Defaults to 0, which
+    // collapses `pathfind`'s `f = g + h` ordering down to the original
+    // greedy-by-heuristic behavior.
+    fn cost(&self, from: &Self::Solution, to: &Self::Solution) -> f64 {
+        let _ = (from, to);
+        0.0
+    }
+
+    // `pathfind` maximizes `f = g + h`, so this is an optimistic (not
+    // pessimistic) bound on the fitness still reachable from `node`.
+    fn heuristic(&self, node: &Self::Solution) -> f64;
+
+    fn fitness_bucket_count(&self) -> Option<usize> {
+        None
+    }
+
     fn fitness_estimate(&mut self, solution: &Self::Solution) -> f64;
 
     fn is_goal(&self, solution: &Self::Solution, fitness: f64) -> bool;
@@ -178,37 +303,173 @@ trait Searchable: Clone {
 
     fn output(&mut self, solution: &Self::Solution, fitness: f64);
 
+    fn anneal(&mut self, time_limit: f64) -> (Self::Solution, f64) {
+        const T0: f64 = 100.0;
+        const T1: f64 = 0.01;
 
-    fn heap_entry(&mut self, solution: Self::Solution) -> HeapEntry<Self::Solution> {
-        HeapEntry {
-            value: self.fitness_estimate(&solution),
-            item: solution,
+        let clock = Instant::now();
+
+        let mut current = self.start();
+        let mut current_fitness = self.fitness_estimate(&current);
+
+        let mut best = current.clone();
+        let mut best_fitness = current_fitness;
+
+        loop {
+            let t = clock.elapsed().as_secs_f64() / time_limit;
+
+            if t >= 1.0 {
+                break;
+            }
+
+            let temperature = T0 * (T1 / T0).powf(t);
+
+            let neighbors = self.neighbors(&current);
+            let neighbor = neighbors[thread_rng().gen_range(0, neighbors.len())].clone();
+            let neighbor_fitness = self.fitness_estimate(&neighbor);
+
+            let delta = neighbor_fitness - current_fitness;
+
+            if delta > 0.0 || thread_rng().gen_range(0.0, 1.0) < (delta / temperature).exp() {
+                current = neighbor;
+                current_fitness = neighbor_fitness;
+
+                if current_fitness > best_fitness {
+                    best = current.clone();
+                    best_fitness = current_fitness;
+                }
+            }
         }
+
+        (best, best_fitness)
     }
 
-    fn pathfind(&mut self) -> Self::Solution {
-        let mut open = BinaryHeap::new();
+    fn beam_search(&mut self, beam_width: usize, max_depth: usize) -> (Self::Solution, f64) {
         let mut seen = HashSet::new();
 
         let start = self.start();
         seen.insert(start.clone());
-        open.push(self.heap_entry(start.clone()));
+        let start_fitness = self.fitness_estimate(&start);
+
+        let mut beam = vec![(start, start_fitness)];
+
+        let mut best = beam[0].0.clone();
+        let mut best_fitness = start_fitness;
 
+        for _ in 0..max_depth {
+            let mut candidates = Vec::new();
 
-        while let Some(current_heap_entry) = open.pop() {
-            let HeapEntry { item: current, value: current_fitness } = current_heap_entry;
+            for (state, _) in &beam {
+                for neighbor in self.neighbors(state) {
+                    if seen.contains(&neighbor) { continue }
 
-            self.output(&current, current_fitness);
+                    seen.insert(neighbor.clone());
+                    let fitness = self.fitness_estimate(&neighbor);
+                    candidates.push((neighbor, fitness));
+                }
+            }
+
+            if candidates.is_empty() {
+                break;
+            }
 
-            if self.is_goal(&current, current_fitness) {
-                return current.clone();
+            candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+            candidates.truncate(beam_width);
+
+            for (state, fitness) in &candidates {
+                if *fitness > best_fitness {
+                    best = state.clone();
+                    best_fitness = *fitness;
+                }
+            }
+
+            beam = candidates;
+        }
+
+        (best, best_fitness)
+    }
+
+    fn pathfind(&mut self) -> Self::Solution {
+        match self.fitness_bucket_count() {
+            Some(bucket_count) => self.pathfind_bucketed(bucket_count),
+            None => self.pathfind_heap(),
+        }
+    }
+
+    fn pathfind_heap(&mut self) -> Self::Solution {
+        let mut open = BinaryHeap::new();
+        let mut g_score: HashMap<Self::Solution, f64> = HashMap::new();
+
+        let start = self.start();
+        g_score.insert(start.clone(), 0.0);
+        let start_h = self.heuristic(&start);
+        open.push(HeapEntry { value: start_h, item: (0.0, start_h, start) });
+
+        while let Some(HeapEntry { item: (g, fitness, current), .. }) = open.pop() {
+            if g > *g_score.get(&current).unwrap() {
+                continue;
+            }
+
+            self.output(&current, fitness);
+
+            if self.is_goal(&current, fitness) {
+                return current;
             }
 
             for neighbor in self.neighbors(&current) {
-                if seen.contains(&neighbor) { continue }
+                let tentative_g = g + self.cost(&current, &neighbor);
+
+                let is_better = match g_score.get(&neighbor) {
+                    Some(&known_g) => tentative_g < known_g,
+                    None => true,
+                };
+
+                if is_better {
+                    g_score.insert(neighbor.clone(), tentative_g);
+                    let h = self.heuristic(&neighbor);
+                    let f = tentative_g + h;
+                    open.push(HeapEntry { value: f, item: (tentative_g, h, neighbor) });
+                }
+            }
+        }
+
+        unreachable!()
+    }
+
+    fn pathfind_bucketed(&mut self, bucket_count: usize) -> Self::Solution {
+        let mut open = BucketQueue::new(bucket_count);
+        let mut g_score: HashMap<Self::Solution, f64> = HashMap::new();
+
+        let start = self.start();
+        g_score.insert(start.clone(), 0.0);
+        let start_h = self.heuristic(&start);
+        open.push(start_h.round() as usize, (0.0, start_h, start));
 
-                seen.insert(neighbor.clone());
-                open.push(self.heap_entry(neighbor.clone()));
+        while let Some((_, (g, fitness, current))) = open.pop() {
+            if g > *g_score.get(&current).unwrap() {
+                continue;
+            }
+
+            self.output(&current, fitness);
+
+            if self.is_goal(&current, fitness) {
+                return current;
+            }
+
+            for neighbor in self.neighbors(&current) {
+                let tentative_g = g + self.cost(&current, &neighbor);
+
+                let is_better = match g_score.get(&neighbor) {
+                    Some(&known_g) => tentative_g < known_g,
+                    None => true,
+                };
+
+                if is_better {
+                    g_score.insert(neighbor.clone(), tentative_g);
+                    let h = self.heuristic(&neighbor);
+                    let f = tentative_g + h;
+                    open.push(f.round() as usize, (tentative_g, h, neighbor));
+                }
             }
         }
 
@@ -216,6 +477,84 @@ trait Searchable: Clone {
     }
 }
 
+// Global, lazily-started worker pool backing test_on_data, so evaluating a
+// candidate doesn't pay OS thread spawn/join cost on every call.
+fn eval_pool() -> &'static ThreadPool {
+    static POOL: OnceLock<ThreadPool> = OnceLock::new();
+    POOL.get_or_init(|| {
+        let n_threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        ThreadPool::new(n_threads)
+    })
+}
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+struct ThreadPool {
+    sender: mpsc::Sender<Job>,
+    size: usize,
+}
+
+impl ThreadPool {
+    fn new(size: usize) -> ThreadPool {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for _ in 0..size {
+            let receiver = Arc::clone(&receiver);
+
+            thread::spawn(move || {
+                while let Ok(job) = receiver.lock().unwrap().recv() {
+                    job();
+                }
+            });
+        }
+
+        ThreadPool { sender, size }
+    }
+
+    fn execute<F: FnOnce() + Send + 'static>(&self, job: F) {
+        self.sender.send(Box::new(job)).unwrap();
+    }
+}
+
+struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    max_nonempty: usize,
+}
+
+impl<T> BucketQueue<T> {
+    fn new(max_priority: usize) -> BucketQueue<T> {
+        BucketQueue {
+            buckets: (0..=max_priority).map(|_| Vec::new()).collect(),
+            max_nonempty: 0,
+        }
+    }
+
+    fn push(&mut self, priority: usize, item: T) {
+        assert!(priority < self.buckets.len(), "priority {} exceeds the bucket queue's max_priority {}", priority, self.buckets.len() - 1);
+
+        if priority > self.max_nonempty {
+            self.max_nonempty = priority;
+        }
+
+        self.buckets[priority].push(item);
+    }
+
+    fn pop(&mut self) -> Option<(usize, T)> {
+        loop {
+            if let Some(item) = self.buckets[self.max_nonempty].pop() {
+                return Some((self.max_nonempty, item));
+            }
+
+            if self.max_nonempty == 0 {
+                return None;
+            }
+
+            self.max_nonempty -= 1;
+        }
+    }
+}
+
 struct HeapEntry<T> {
     value: f64,
     item: T,